@@ -271,6 +271,35 @@ fn format_flags() {
     );
 }
 #[test]
+fn format_sign_and_width() {
+    // A sign is always a flag, never part of the width that follows it.
+    same(
+        "{:-5}",
+        &[NextArgument(Argument {
+            position: ArgumentImplicitlyIs(0),
+            format: FormatSpec {
+                flags: (1 << FlagSignMinus as u32),
+                width: CountIs(5),
+                ..fmtdflt()
+            },
+        })],
+    );
+    same(
+        "{:+05}",
+        &[NextArgument(Argument {
+            position: ArgumentImplicitlyIs(0),
+            format: FormatSpec {
+                flags: (1 << FlagSignPlus as u32) | (1 << FlagSignAwareZeroPad as u32),
+                width: CountIs(5),
+                ..fmtdflt()
+            },
+        })],
+    );
+    musterr("{:5-}");
+    musterr("{:5+}");
+    musterr("{:.5-}");
+}
+#[test]
 fn format_mixture() {
     same(
         "abcd {3:x} efg",
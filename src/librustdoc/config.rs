@@ -265,6 +265,10 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
     crate document_hidden: bool,
     /// If `true`, generate a JSON file in the crate folder instead of HTML redirection files.
     crate generate_redirect_map: bool,
+    /// Identifiers highlighted like `Option` and `Result` in source code.
+    crate highlight_prelude_types: Vec<String>,
+    /// Identifiers highlighted like `Some` and `None` in source code.
+    crate highlight_prelude_values: Vec<String>,
     crate unstable_features: rustc_feature::UnstableFeatures,
 }
 
@@ -580,6 +584,8 @@ fn println_condition(condition: Condition) {
         let document_hidden = matches.opt_present("document-hidden-items");
         let run_check = matches.opt_present("check");
         let generate_redirect_map = matches.opt_present("generate-redirect-map");
+        let highlight_prelude_types = matches.opt_strs("highlight-prelude-type");
+        let highlight_prelude_values = matches.opt_strs("highlight-prelude-value");
 
         let (lint_opts, describe_lints, lint_cap) = get_cmd_lint_options(matches, error_format);
 
@@ -638,6 +644,8 @@ fn println_condition(condition: Condition) {
                 document_private,
                 document_hidden,
                 generate_redirect_map,
+                highlight_prelude_types,
+                highlight_prelude_values,
                 unstable_features: rustc_feature::UnstableFeatures::from_environment(
                     crate_name.as_deref(),
                 ),
@@ -7,16 +7,93 @@
 
 use crate::html::escape::Escape;
 
+use std::collections::VecDeque;
 use std::fmt::Display;
 use std::iter::Peekable;
+use std::ops::Range;
 
+use rustc_lexer::unescape::{unescape_literal, Mode};
 use rustc_lexer::{LiteralKind, TokenKind};
-use rustc_span::edition::Edition;
+use rustc_span::edition::{Edition, DEFAULT_EDITION};
 use rustc_span::symbol::Symbol;
-use rustc_span::with_default_session_globals;
+use rustc_span::{with_default_session_globals, SessionGlobals, SESSION_GLOBALS};
 
 use super::format::Buffer;
 
+/// Optional tweaks to the way code is classified and written out.
+///
+/// The default value highlights code exactly like rustdoc always has.
+#[derive(Clone, Copy, Default)]
+crate struct HighlightOptions<'a> {
+    /// Identifiers highlighted like `Option` and `Result`, in addition to those.
+    crate prelude_types: &'a [&'a str],
+    /// Identifiers highlighted like `Some` and `None`, in addition to those.
+    crate prelude_values: &'a [&'a str],
+    /// Merge consecutive tokens of the same class into a single `<span>`.
+    crate coalesce_spans: bool,
+    /// Give escape sequences like `\n` their own class inside of literals.
+    crate highlight_escapes: bool,
+    /// Class given to the tooltip instead of `tooltip`.
+    crate tooltip_class: Option<&'a str>,
+    /// HTML shown as the tooltip instead of `ⓘ`.
+    crate tooltip_glyph: Option<&'a str>,
+    /// Give built-in macros a class of their own, distinct from user-defined ones.
+    crate highlight_builtin_macros: bool,
+    /// Names of the built-in macros instead of `STD_MACROS`.
+    crate builtin_macros: Option<&'a [&'a str]>,
+    /// Don't wrap the code in a `<div class="example-wrap">`.
+    crate skip_example_wrap: bool,
+    /// Don't wrap the code in a `<pre class="rust">`.
+    crate skip_pre: bool,
+    /// Called with every classified token, before `coalesce_spans` merges
+    /// anything. Returning `Some` replaces the token's HTML, e.g. to turn an
+    /// identifier into a link, and keeps the token out of merged spans.
+    crate token_hook: Option<&'a dyn Fn(Class, &str) -> Option<String>>,
+    /// Identifiers given the `LinkableIdent` class, so that they can be
+    /// styled or turned into links later on.
+    crate linkable_idents: &'a [&'a str],
+}
+
+/// The commonly used macros of the standard library.
+const STD_MACROS: &[&str] = &[
+    "assert",
+    "assert_eq",
+    "assert_ne",
+    "cfg",
+    "column",
+    "compile_error",
+    "concat",
+    "dbg",
+    "debug_assert",
+    "debug_assert_eq",
+    "debug_assert_ne",
+    "env",
+    "eprint",
+    "eprintln",
+    "file",
+    "format",
+    "format_args",
+    "include",
+    "include_bytes",
+    "include_str",
+    "line",
+    "macro_rules",
+    "matches",
+    "module_path",
+    "option_env",
+    "panic",
+    "print",
+    "println",
+    "stringify",
+    "thread_local",
+    "todo",
+    "unimplemented",
+    "unreachable",
+    "vec",
+    "write",
+    "writeln",
+];
+
 /// Highlights `src`, returning the HTML output.
 crate fn render_with_highlighting(
     src: &str,
@@ -25,49 +102,147 @@
     playground_button: Option<&str>,
     tooltip: Option<(Option<Edition>, &str)>,
     edition: Edition,
+    options: HighlightOptions<'_>,
 ) {
     debug!("highlighting: ================\n{}\n==============", src);
     if let Some((edition_info, class)) = tooltip {
         write!(
             out,
-            "<div class='information'><div class='tooltip {}'{}>ⓘ</div></div>",
+            "<div class='information'><div class='{} {}'{}>{}</div></div>",
+            options.tooltip_class.unwrap_or("tooltip"),
             class,
             if let Some(edition_info) = edition_info {
                 format!(" data-edition=\"{}\"", edition_info)
             } else {
                 String::new()
             },
+            options.tooltip_glyph.unwrap_or("ⓘ"),
         );
     }
 
-    write_header(out, class);
-    write_code(out, &src, edition);
-    write_footer(out, playground_button);
+    write_header(out, class, options);
+    write_code(out, &src, edition, options);
+    write_footer(out, playground_button, options);
 }
 
-fn write_header(out: &mut Buffer, class: Option<&str>) {
-    write!(out, "<div class=\"example-wrap\"><pre class=\"rust {}\">\n", class.unwrap_or_default());
+fn write_header(out: &mut Buffer, class: Option<&str>, options: HighlightOptions<'_>) {
+    if !options.skip_example_wrap {
+        out.write_str("<div class=\"example-wrap\">");
+    }
+    if !options.skip_pre {
+        write!(out, "<pre class=\"rust {}\">\n", class.unwrap_or_default());
+    }
 }
 
-fn write_code(out: &mut Buffer, src: &str, edition: Edition) {
+fn write_code(out: &mut Buffer, src: &str, edition: Edition, options: HighlightOptions<'_>) {
     // This replace allows to fix how the code source with DOS backline characters is displayed.
     let src = src.replace("\r\n", "\n");
-    Classifier::new(&src, edition).highlight(&mut |highlight| {
+    write_tokens(out, TokenIter { src: &src }, edition, options);
+}
+
+/// Highlights already lexed `tokens`, whose texts must concatenate to the
+/// highlighted source, writing the HTML into `out`.
+///
+/// Unlike `render_with_highlighting`, this doesn't write any header or footer.
+crate fn write_tokens<'a>(
+    out: &mut Buffer,
+    tokens: impl Iterator<Item = (TokenKind, &'a str)>,
+    edition: Edition,
+    options: HighlightOptions<'a>,
+) {
+    let classifier = Classifier::from_tokens(tokens, edition, options);
+    if options.coalesce_spans {
+        let mut coalescer = Coalescer { options, ..Coalescer::default() };
+        classifier.highlight(&mut |highlight| coalescer.push(out, highlight));
+        coalescer.flush(out);
+        return;
+    }
+    classifier.highlight(&mut |highlight| {
         match highlight {
-            Highlight::Token { text, class } => string(out, Escape(text), class),
+            Highlight::Token { text, class } => token(out, text, class, options),
             Highlight::EnterSpan { class } => enter_span(out, class),
             Highlight::ExitSpan => exit_span(out),
         };
     });
 }
 
-fn write_footer(out: &mut Buffer, playground_button: Option<&str>) {
-    write!(out, "</pre>{}</div>\n", playground_button.unwrap_or_default());
+/// Buffers classified tokens so that runs of the same class are written as a
+/// single span.
+///
+/// Whitespace between two tokens of the same class ends up inside the merged
+/// span, whitespace anywhere else is written out as is. Tokens replaced by the
+/// `token_hook` are written out on their own.
+#[derive(Default)]
+struct Coalescer<'a> {
+    pending: Option<(Class, String)>,
+    whitespace: String,
+    options: HighlightOptions<'a>,
+}
+
+impl Coalescer<'_> {
+    fn push(&mut self, out: &mut Buffer, highlight: Highlight<'_>) {
+        match highlight {
+            Highlight::Token { text, class: Some(class) } => {
+                if let Some(html) = self.options.token_hook.and_then(|hook| hook(class, text)) {
+                    self.flush(out);
+                    return out.write_str(&html);
+                }
+                match self.pending {
+                    Some((pending_class, ref mut pending)) if pending_class == class => {
+                        pending.push_str(&self.whitespace);
+                        pending.push_str(text);
+                        self.whitespace.clear();
+                    }
+                    _ => {
+                        self.flush(out);
+                        self.pending = Some((class, text.to_string()));
+                    }
+                }
+            }
+            Highlight::Token { text, class: None }
+                if self.pending.is_some() && text.trim().is_empty() =>
+            {
+                self.whitespace.push_str(text);
+            }
+            Highlight::Token { text, class: None } => {
+                self.flush(out);
+                string(out, Escape(text), None);
+            }
+            Highlight::EnterSpan { class } => {
+                self.flush(out);
+                enter_span(out, class);
+            }
+            Highlight::ExitSpan => {
+                self.flush(out);
+                exit_span(out);
+            }
+        }
+    }
+
+    /// Writes out the buffered tokens.
+    fn flush(&mut self, out: &mut Buffer) {
+        if let Some((class, text)) = self.pending.take() {
+            string(out, Escape(&text), Some(class));
+        }
+        string(out, Escape(&self.whitespace), None);
+        self.whitespace.clear();
+    }
+}
+
+fn write_footer(out: &mut Buffer, playground_button: Option<&str>, options: HighlightOptions<'_>) {
+    if !options.skip_pre {
+        out.write_str("</pre>");
+    }
+    out.write_str(playground_button.unwrap_or_default());
+    if !options.skip_example_wrap {
+        out.write_str("</div>");
+    }
+    out.write_str("\n");
 }
 
 /// How a span of text is classified. Mostly corresponds to token kinds.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
-enum Class {
+crate enum Class {
     Comment,
     DocComment,
     Attribute,
@@ -77,20 +252,26 @@ enum Class {
     Self_,
     Op,
     Macro,
+    /// A built-in macro, see `HighlightOptions::highlight_builtin_macros`.
+    BuiltinMacro,
     MacroNonTerminal,
     String,
     Number,
     Bool,
     Ident,
+    /// An identifier listed in `HighlightOptions::linkable_idents`.
+    LinkableIdent,
     Lifetime,
     PreludeTy,
     PreludeVal,
     QuestionMark,
+    /// An escape sequence inside of a string or character literal.
+    Escape,
 }
 
 impl Class {
     /// Returns the css class expected by rustdoc for each `Class`.
-    fn as_html(self) -> &'static str {
+    crate fn as_html(self) -> &'static str {
         match self {
             Class::Comment => "comment",
             Class::DocComment => "doccomment",
@@ -100,15 +281,18 @@ fn as_html(self) -> &'static str {
             Class::Self_ => "self",
             Class::Op => "op",
             Class::Macro => "macro",
+            Class::BuiltinMacro => "macro builtin-macro",
             Class::MacroNonTerminal => "macro-nonterminal",
             Class::String => "string",
             Class::Number => "number",
             Class::Bool => "bool-val",
             Class::Ident => "ident",
+            Class::LinkableIdent => "ident linkable",
             Class::Lifetime => "lifetime",
             Class::PreludeTy => "prelude-ty",
             Class::PreludeVal => "prelude-val",
             Class::QuestionMark => "question-mark",
+            Class::Escape => "escape",
         }
     }
 }
@@ -136,25 +320,111 @@ fn next(&mut self) -> Option<(TokenKind, &'a str)> {
     }
 }
 
+/// Classifies `src` without writing any HTML, lazily yielding the byte range of
+/// every region of `src` along with its class.
+// Not called by rustdoc yet.
+#[allow(dead_code)]
+crate fn classified_ranges<'a>(
+    src: &'a str,
+    edition: Edition,
+    options: HighlightOptions<'a>,
+) -> ClassifiedRanges<'a> {
+    ClassifiedRanges {
+        classifier: Classifier::new(src, edition, options),
+        pending: VecDeque::new(),
+        spans: Vec::new(),
+        pos: 0,
+        session_globals: SessionGlobals::new(DEFAULT_EDITION),
+    }
+}
+
+/// An iterator over the classified regions of some source code, see
+/// `classified_ranges`.
+///
+/// The regions are contiguous and cover all of the source. A token without a
+/// class of its own inside of a span (e.g. the brackets of an attribute) gets
+/// the class of the span.
+crate struct ClassifiedRanges<'a> {
+    classifier: Classifier<'a>,
+    /// What the last classification step produced and wasn't yielded yet.
+    pending: VecDeque<Highlight<'a>>,
+    spans: Vec<Class>,
+    pos: usize,
+    /// Keywords are looked up in a session of our own, like `Classifier::highlight` does.
+    session_globals: SessionGlobals,
+}
+
+impl<'a> Iterator for ClassifiedRanges<'a> {
+    type Item = (Option<Class>, Range<usize>);
+
+    fn next(&mut self) -> Option<(Option<Class>, Range<usize>)> {
+        loop {
+            match self.pending.pop_front() {
+                Some(Highlight::Token { text, class }) => {
+                    let range = self.pos..self.pos + text.len();
+                    self.pos = range.end;
+                    return Some((class.or_else(|| self.spans.last().copied()), range));
+                }
+                Some(Highlight::EnterSpan { class }) => self.spans.push(class),
+                Some(Highlight::ExitSpan) => {
+                    self.spans.pop();
+                }
+                None => {
+                    let (classifier, pending) = (&mut self.classifier, &mut self.pending);
+                    let advanced = SESSION_GLOBALS.set(&self.session_globals, || {
+                        classifier.step(&mut |highlight| pending.push_back(highlight))
+                    });
+                    if !advanced {
+                        return None;
+                    }
+                }
+            }
+        }
+    }
+}
+
 /// Processes program tokens, classifying strings of text by highlighting
 /// category (`Class`).
-struct Classifier<'a> {
-    tokens: Peekable<TokenIter<'a>>,
+///
+/// Tokens are lexed from a `&str` by default, but any stream of
+/// `(TokenKind, &str)` pairs can be classified through `from_tokens`.
+struct Classifier<'a, I: Iterator<Item = (TokenKind, &'a str)> = TokenIter<'a>> {
+    tokens: Peekable<I>,
     in_attribute: bool,
-    in_macro: bool,
+    /// The class of the macro name just seen, which its `!` gets as well.
+    in_macro: Option<Class>,
     in_macro_nonterminal: bool,
+    /// The kind of the token classified before the current one.
+    previous: Option<TokenKind>,
+    /// How many lists of generic arguments look like they are still open.
+    generic_depth: usize,
     edition: Edition,
+    options: HighlightOptions<'a>,
 }
 
 impl<'a> Classifier<'a> {
-    fn new(src: &str, edition: Edition) -> Classifier<'_> {
-        let tokens = TokenIter { src }.peekable();
+    fn new(src: &'a str, edition: Edition, options: HighlightOptions<'a>) -> Classifier<'a> {
+        Classifier::from_tokens(TokenIter { src }, edition, options)
+    }
+}
+
+impl<'a, I: Iterator<Item = (TokenKind, &'a str)>> Classifier<'a, I> {
+    /// Classifies already lexed tokens, whose texts must concatenate to the
+    /// highlighted source.
+    fn from_tokens(
+        tokens: I,
+        edition: Edition,
+        options: HighlightOptions<'a>,
+    ) -> Classifier<'a, I> {
         Classifier {
-            tokens,
+            tokens: tokens.peekable(),
             in_attribute: false,
-            in_macro: false,
+            in_macro: None,
             in_macro_nonterminal: false,
+            previous: None,
+            generic_depth: 0,
             edition,
+            options,
         }
     }
 
@@ -163,12 +433,29 @@ fn new(src: &str, edition: Edition) -> Classifier<'_> {
     /// The general structure for this method is to iterate over each token,
     /// possibly giving it an HTML span with a class specifying what flavor of
     /// token is used.
+    ///
+    /// Keywords are looked up in a fresh session, so the output doesn't depend
+    /// on whatever the caller's session has interned so far.
     fn highlight(mut self, sink: &mut dyn FnMut(Highlight<'a>)) {
-        with_default_session_globals(|| {
-            while let Some((token, text)) = self.tokens.next() {
+        with_default_session_globals(|| while self.step(sink) {})
+    }
+
+    /// Classifies the next token, and maybe a couple of following ones as
+    /// well. Returns `false` once there are no tokens left.
+    fn step(&mut self, sink: &mut dyn FnMut(Highlight<'a>)) -> bool {
+        match self.tokens.next() {
+            Some((token, text)) => {
                 self.advance(token, text, sink);
+                self.previous = Some(token);
+                // A stray comparison taken for generics only lasts until the
+                // end of the statement or block.
+                if matches!(token, TokenKind::Semi | TokenKind::OpenBrace | TokenKind::CloseBrace) {
+                    self.generic_depth = 0;
+                }
+                true
             }
-        })
+            None => false,
+        }
     }
 
     /// Single step of highlighting. This will classify `token`, but maybe also
@@ -187,10 +474,7 @@ fn advance(&mut self, token: TokenKind, text: &'a str, sink: &mut dyn FnMut(High
             }
             // Consider this as part of a macro invocation if there was a
             // leading identifier.
-            TokenKind::Bang if self.in_macro => {
-                self.in_macro = false;
-                Class::Macro
-            }
+            TokenKind::Bang if self.in_macro.is_some() => self.in_macro.take().unwrap(),
 
             // Assume that '&' or '*' is the reference or dereference operator
             // or a reference or pointer type. Unless, of course, it looks like
@@ -214,6 +498,53 @@ fn advance(&mut self, token: TokenKind, text: &'a str, sink: &mut dyn FnMut(High
                 _ => Class::RefKeyWord,
             },
 
+            // Operators the lexer splits into several tokens are highlighted
+            // as a single one.
+            TokenKind::Dot if lookahead == Some(TokenKind::Dot) => {
+                let _dot = self.tokens.next();
+                if self.peek() == Some(TokenKind::Eq) {
+                    let _eq = self.tokens.next();
+                    sink(Highlight::Token { text: "..=", class: Some(Class::Op) });
+                } else {
+                    sink(Highlight::Token { text: "..", class: Some(Class::Op) });
+                }
+                return;
+            }
+            TokenKind::Colon if lookahead == Some(TokenKind::Colon) => {
+                return self.joined_op("::", sink);
+            }
+            TokenKind::Minus if lookahead == Some(TokenKind::Gt) => {
+                return self.joined_op("->", sink);
+            }
+            TokenKind::Eq if lookahead == Some(TokenKind::Gt) => return self.joined_op("=>", sink),
+            // A `<` glued to a path opens generics, and the matching `>` isn't
+            // joined with a following `=`, like in `let v: Vec<u8>= w;`.
+            TokenKind::Lt
+                if lookahead != Some(TokenKind::Eq)
+                    && matches!(self.previous, Some(TokenKind::Ident) | Some(TokenKind::Colon)) =>
+            {
+                self.generic_depth += 1;
+                Class::Op
+            }
+            TokenKind::Gt if self.generic_depth > 0 => {
+                self.generic_depth -= 1;
+                Class::Op
+            }
+            // `<<=` and `>>=` are left as separate tokens: without parsing,
+            // they can't be told apart from closing generics followed by `=`.
+            TokenKind::Lt | TokenKind::Gt if self.previous == Some(token) => Class::Op,
+            TokenKind::Eq | TokenKind::Lt | TokenKind::Gt | TokenKind::Bang
+                if lookahead == Some(TokenKind::Eq) =>
+            {
+                let op = match token {
+                    TokenKind::Eq => "==",
+                    TokenKind::Lt => "<=",
+                    TokenKind::Gt => ">=",
+                    _ => "!=",
+                };
+                return self.joined_op(op, sink);
+            }
+
             // Operators.
             TokenKind::Minus
             | TokenKind::Plus
@@ -285,7 +616,23 @@ fn advance(&mut self, token: TokenKind, text: &'a str, sink: &mut dyn FnMut(High
                 }
                 return no_highlight(sink);
             }
-            TokenKind::Literal { kind, .. } => match kind {
+            TokenKind::Literal { kind, suffix_start } => match kind {
+                // Text literals with escape sequences worth pointing out.
+                LiteralKind::Byte { terminated }
+                | LiteralKind::Char { terminated }
+                | LiteralKind::Str { terminated }
+                | LiteralKind::ByteStr { terminated }
+                    if self.options.highlight_escapes =>
+                {
+                    let (mode, prefix_len) = match kind {
+                        LiteralKind::Byte { .. } => (Mode::Byte, 2),
+                        LiteralKind::Char { .. } => (Mode::Char, 1),
+                        LiteralKind::Str { .. } => (Mode::Str, 1),
+                        _ => (Mode::ByteStr, 2),
+                    };
+                    let end = if terminated { suffix_start - 1 } else { suffix_start };
+                    return highlight_escapes(text, mode, prefix_len..end, sink);
+                }
                 // Text literals.
                 LiteralKind::Byte { .. }
                 | LiteralKind::Char { .. }
@@ -297,8 +644,28 @@ fn advance(&mut self, token: TokenKind, text: &'a str, sink: &mut dyn FnMut(High
                 LiteralKind::Float { .. } | LiteralKind::Int { .. } => Class::Number,
             },
             TokenKind::Ident | TokenKind::RawIdent if lookahead == Some(TokenKind::Bang) => {
-                self.in_macro = true;
-                Class::Macro
+                let builtin_macros = self.options.builtin_macros.unwrap_or(STD_MACROS);
+                let class =
+                    if self.options.highlight_builtin_macros && builtin_macros.contains(&text) {
+                        Class::BuiltinMacro
+                    } else {
+                        Class::Macro
+                    };
+                self.in_macro = Some(class);
+                class
+            }
+            // An `f` directly followed by a string is an f-string. The compiler
+            // doesn't accept them, but examples may still show them, so give the
+            // prefix the class of the string instead of highlighting an `f`
+            // identifier. The string itself is classified as usual.
+            TokenKind::Ident
+                if text == "f"
+                    && matches!(
+                        lookahead,
+                        Some(TokenKind::Literal { kind: LiteralKind::Str { .. }, .. })
+                    ) =>
+            {
+                Class::String
             }
             TokenKind::Ident => match text {
                 "ref" | "mut" => Class::RefKeyWord,
@@ -312,6 +679,9 @@ fn advance(&mut self, token: TokenKind, text: &'a str, sink: &mut dyn FnMut(High
                     self.in_macro_nonterminal = false;
                     Class::MacroNonTerminal
                 }
+                _ if self.options.prelude_types.contains(&text) => Class::PreludeTy,
+                _ if self.options.prelude_values.contains(&text) => Class::PreludeVal,
+                _ if self.options.linkable_idents.contains(&text) => Class::LinkableIdent,
                 _ => Class::Ident,
             },
             TokenKind::RawIdent => Class::Ident,
@@ -322,11 +692,50 @@ fn advance(&mut self, token: TokenKind, text: &'a str, sink: &mut dyn FnMut(High
         sink(Highlight::Token { text, class: Some(class) });
     }
 
+    /// Highlights the current token and the next one together as `op`.
+    fn joined_op(&mut self, op: &'static str, sink: &mut dyn FnMut(Highlight<'a>)) {
+        let _next = self.tokens.next();
+        sink(Highlight::Token { text: op, class: Some(Class::Op) });
+    }
+
     fn peek(&mut self) -> Option<TokenKind> {
         self.tokens.peek().map(|(toke_kind, _text)| *toke_kind)
     }
 }
 
+/// Writes a string or character literal, giving the escape sequences found in
+/// `text[body]` their own `Escape` class inside of the `String` span.
+fn highlight_escapes<'a>(
+    text: &'a str,
+    mode: Mode,
+    body: Range<usize>,
+    sink: &mut dyn FnMut(Highlight<'a>),
+) {
+    let mut escapes = Vec::new();
+    unescape_literal(&text[body.clone()], mode, &mut |range, result| {
+        if result.is_ok() && text[body.start + range.start..].starts_with('\\') {
+            escapes.push(body.start + range.start..body.start + range.end);
+        }
+    });
+    if escapes.is_empty() {
+        return sink(Highlight::Token { text, class: Some(Class::String) });
+    }
+
+    sink(Highlight::EnterSpan { class: Class::String });
+    let mut pos = 0;
+    for escape in escapes {
+        if pos < escape.start {
+            sink(Highlight::Token { text: &text[pos..escape.start], class: None });
+        }
+        pos = escape.end;
+        sink(Highlight::Token { text: &text[escape], class: Some(Class::Escape) });
+    }
+    if pos < text.len() {
+        sink(Highlight::Token { text: &text[pos..], class: None });
+    }
+    sink(Highlight::ExitSpan);
+}
+
 /// Called when we start processing a span of text that should be highlighted.
 /// The `Class` argument specifies how it should be highlighted.
 fn enter_span(out: &mut Buffer, klass: Class) {
@@ -338,6 +747,17 @@ fn exit_span(out: &mut Buffer) {
     out.write_str("</span>");
 }
 
+/// Called for a token of highlighted code, which is written out with `string`
+/// unless `HighlightOptions::token_hook` provides its HTML.
+fn token(out: &mut Buffer, text: &str, klass: Option<Class>, options: HighlightOptions<'_>) {
+    if let (Some(hook), Some(klass)) = (options.token_hook, klass) {
+        if let Some(html) = hook(klass, text) {
+            return out.write_str(&html);
+        }
+    }
+    string(out, Escape(text), klass);
+}
+
 /// Called for a span of text. If the text should be highlighted differently
 /// from the surrounding text, then the `Class` argument will be a value other
 /// than `None`.
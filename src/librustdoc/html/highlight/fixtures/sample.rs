@@ -9,6 +9,11 @@ fn main() {
     let _ = *foo;
     mac!(foo, &mut bar);
     assert!(self.length < N && index <= self.length);
+    ::std::env::var("gateau").is_ok();
+    for _ in 0..10 {}
+    let _ = ..=3;
+    foo >>= 1;
+    foo <<= 1;
 }
 
 macro_rules! bar {
@@ -1,7 +1,45 @@
-use super::write_code;
+use super::{classified_ranges, render_with_highlighting, write_code, write_tokens};
+use super::{Class, HighlightOptions};
 use crate::html::format::Buffer;
 use expect_test::expect_file;
-use rustc_span::edition::Edition;
+use rustc_lexer::{Base, LiteralKind, TokenKind};
+use rustc_span::edition::{Edition, ALL_EDITIONS};
+use rustc_span::symbol::Symbol;
+use rustc_span::with_session_globals;
+use std::cell::RefCell;
+
+/// Classifies `src` and returns each non-whitespace token with its class.
+///
+/// A token without a class of its own inherits the class of the span it is
+/// nested in (e.g. the brackets of an attribute), which is how it ends up
+/// being rendered.
+fn classify_with(src: &str, options: HighlightOptions<'_>) -> Vec<(String, Option<Class>)> {
+    classify_edition(src, Edition::Edition2018, options)
+}
+
+fn classify_edition(
+    src: &str,
+    edition: Edition,
+    options: HighlightOptions<'_>,
+) -> Vec<(String, Option<Class>)> {
+    classified_ranges(src, edition, options)
+        .map(|(class, range)| (src[range].to_string(), class))
+        .filter(|(text, _)| !text.trim().is_empty())
+        .collect()
+}
+
+fn classify(src: &str) -> Vec<(String, Option<Class>)> {
+    classify_with(src, HighlightOptions::default())
+}
+
+/// Asserts that `text` is classified as `class` wherever it appears in `tokens`.
+fn assert_class(tokens: &[(String, Option<Class>)], text: &str, class: Option<Class>) {
+    let found: Vec<_> = tokens.iter().filter(|(t, _)| t == text).collect();
+    assert!(!found.is_empty(), "`{}` not found in {:?}", text, tokens);
+    for (_, c) in found {
+        assert_eq!(*c, class, "wrong class for `{}` in {:?}", text, tokens);
+    }
+}
 
 const STYLE: &str = r#"
 <style>
@@ -20,10 +58,21 @@ fn test_html_highlighting() {
     let src = include_str!("fixtures/sample.rs");
     let html = {
         let mut out = Buffer::new();
-        write_code(&mut out, src, Edition::Edition2018);
+        write_code(&mut out, src, Edition::Edition2018, HighlightOptions::default());
         format!("{}<pre><code>{}</code></pre>\n", STYLE, out.into_inner())
     };
     expect_file!["fixtures/sample.html"].assert_eq(&html);
+
+    let tokens = classify(src);
+    assert_class(&tokens, "fn", Some(Class::KeyWord));
+    assert_class(&tokens, "true", Some(Class::Bool));
+    assert_class(&tokens, "&&", Some(Class::Op));
+    assert_class(&tokens, "mut", Some(Class::RefKeyWord));
+    assert_class(&tokens, "self", Some(Class::Self_));
+    assert_class(&tokens, "assert", Some(Class::Macro));
+    assert_class(&tokens, "::", Some(Class::Op));
+    assert_class(&tokens, "..=", Some(Class::Op));
+    assert_class(&tokens, "$", Some(Class::MacroNonTerminal));
 }
 
 #[test]
@@ -32,6 +81,470 @@ fn test_dos_backline() {
     println!(\"foo\");\r\n\
 }\r\n";
     let mut html = Buffer::new();
-    write_code(&mut html, src, Edition::Edition2018);
+    write_code(&mut html, src, Edition::Edition2018, HighlightOptions::default());
     expect_file!["fixtures/dos_line.html"].assert_eq(&html.into_inner());
 }
+
+#[test]
+fn test_custom_prelude() {
+    let src = "fn foo(x: Option<Wrapper>) -> Wrapper { x.unwrap_or(EMPTY) }";
+    let options = HighlightOptions {
+        prelude_types: &["Wrapper"],
+        prelude_values: &["EMPTY"],
+        ..HighlightOptions::default()
+    };
+    let tokens = classify_with(src, options);
+    assert_class(&tokens, "Option", Some(Class::PreludeTy));
+    assert_class(&tokens, "Wrapper", Some(Class::PreludeTy));
+    assert_class(&tokens, "EMPTY", Some(Class::PreludeVal));
+    assert_class(&tokens, "unwrap_or", Some(Class::Ident));
+
+    let tokens = classify(src);
+    assert_class(&tokens, "Wrapper", Some(Class::Ident));
+    assert_class(&tokens, "EMPTY", Some(Class::Ident));
+
+    // Custom names don't override keywords or macro nonterminals.
+    let options =
+        HighlightOptions { prelude_types: &["fn", "Meters"], ..HighlightOptions::default() };
+    let tokens = classify_with("fn f(m: Meters) {} macro_rules! m { ($Meters:ty) => {} }", options);
+    assert_class(&tokens, "fn", Some(Class::KeyWord));
+    assert_class(&tokens, "ty", Some(Class::Ident));
+    assert_eq!(
+        tokens.iter().filter(|(text, _)| text == "Meters").map(|(_, c)| *c).collect::<Vec<_>>(),
+        vec![Some(Class::PreludeTy), Some(Class::MacroNonTerminal)]
+    );
+}
+
+#[test]
+fn test_ref_and_op() {
+    assert_eq!(
+        classify("&x && *y * z"),
+        vec![
+            ("&".to_string(), Some(Class::RefKeyWord)),
+            ("x".to_string(), Some(Class::Ident)),
+            ("&&".to_string(), Some(Class::Op)),
+            ("*".to_string(), Some(Class::RefKeyWord)),
+            ("y".to_string(), Some(Class::Ident)),
+            ("*".to_string(), Some(Class::Op)),
+            ("z".to_string(), Some(Class::Ident)),
+        ]
+    );
+    let tokens = classify("#[derive(Clone)] struct S;");
+    assert_class(&tokens, "#", Some(Class::Attribute));
+    assert_class(&tokens, "derive", Some(Class::Ident));
+    assert_class(&tokens, ";", None);
+}
+
+#[test]
+fn test_multi_char_ops() {
+    for op in &["..", "..=", "::", "->", "=>", "<=", ">=", "==", "!="] {
+        let src = format!("a {} b", op);
+        assert_eq!(
+            classify(&src),
+            vec![
+                ("a".to_string(), Some(Class::Ident)),
+                (op.to_string(), Some(Class::Op)),
+                ("b".to_string(), Some(Class::Ident)),
+            ],
+            "{}",
+            src,
+        );
+    }
+    // Shifts and closing generics aren't glued to a following `=`.
+    for src in &["x >>= 1", "x <<= 1", "let v: Vec<u8>= w;", "let v: Vec<Vec<u8>>= w;"] {
+        let tokens = classify(src);
+        assert!(!tokens.iter().any(|(text, _)| text == ">=" || text == "<="), "{:?}", tokens);
+        assert_class(&tokens, "=", Some(Class::Op));
+    }
+    // Comparisons without spaces still are.
+    for src in &["v.len()>=2", "a>=b", "f::<u8>(x)>=y"] {
+        assert_class(&classify(src), ">=", Some(Class::Op));
+    }
+    // Single tokens are left as they were.
+    let tokens = classify("a.b: c = d < e");
+    assert_class(&tokens, ".", None);
+    assert_class(&tokens, ":", None);
+    assert_class(&tokens, "=", Some(Class::Op));
+    assert_class(&tokens, "<", Some(Class::Op));
+}
+
+#[test]
+fn test_classify_token_stream() {
+    let tokens = vec![
+        (TokenKind::Ident, "let"),
+        (TokenKind::Whitespace, " "),
+        (TokenKind::Ident, "x"),
+        (TokenKind::Whitespace, " "),
+        (TokenKind::Eq, "="),
+        (TokenKind::Whitespace, " "),
+        (TokenKind::Ident, "Some"),
+        (TokenKind::OpenParen, "("),
+        (
+            TokenKind::Literal {
+                kind: LiteralKind::Int { base: Base::Decimal, empty_int: false },
+                suffix_start: 1,
+            },
+            "1",
+        ),
+        (TokenKind::CloseParen, ")"),
+        (TokenKind::Semi, ";"),
+    ];
+    let mut from_tokens = Buffer::new();
+    write_tokens(
+        &mut from_tokens,
+        tokens.into_iter(),
+        Edition::Edition2018,
+        HighlightOptions::default(),
+    );
+    let mut from_src = Buffer::new();
+    write_code(
+        &mut from_src,
+        "let x = Some(1);",
+        Edition::Edition2018,
+        HighlightOptions::default(),
+    );
+    assert_eq!(from_tokens.into_inner(), from_src.into_inner());
+}
+
+#[test]
+fn test_coalesce_spans() {
+    fn render(src: &str, coalesce_spans: bool) -> String {
+        let mut html = Buffer::new();
+        let options = HighlightOptions { coalesce_spans, ..HighlightOptions::default() };
+        write_code(&mut html, src, Edition::Edition2018, options);
+        html.into_inner()
+    }
+
+    assert_eq!(
+        render("x && !y", true),
+        "<span class=\"ident\">x</span> <span class=\"op\">&amp;&amp; !</span>\
+         <span class=\"ident\">y</span>"
+    );
+    assert_eq!(
+        render("a -> b", true),
+        "<span class=\"ident\">a</span> <span class=\"op\">-&gt;</span> \
+         <span class=\"ident\">b</span>"
+    );
+
+    let src = include_str!("fixtures/sample.rs");
+    let coalesced = render(src, true);
+    let plain = render(src, false);
+    assert!(coalesced.len() < plain.len());
+
+    // Both outputs must show the same text, only the markup differs.
+    let strip_tags = |html: &str| {
+        let mut text = String::new();
+        let mut in_tag = false;
+        for c in html.chars() {
+            match c {
+                '<' => in_tag = true,
+                '>' => in_tag = false,
+                _ if !in_tag => text.push(c),
+                _ => {}
+            }
+        }
+        text
+    };
+    assert_eq!(strip_tags(&coalesced), strip_tags(&plain));
+}
+
+#[test]
+fn test_highlight_escapes() {
+    let options = HighlightOptions { highlight_escapes: true, ..HighlightOptions::default() };
+    let src = r#"let s = "a\nb\u{1F600}c"; let c = '\''; let b = b"\x7f\q";"#;
+    let tokens = classify_with(src, options);
+    let strings: Vec<_> = tokens
+        .iter()
+        .filter(|(_, class)| matches!(class, Some(Class::String) | Some(Class::Escape)))
+        .map(|(text, class)| (text.as_str(), class.unwrap()))
+        .collect();
+    assert_eq!(
+        strings,
+        vec![
+            ("\"a", Class::String),
+            ("\\n", Class::Escape),
+            ("b", Class::String),
+            ("\\u{1F600}", Class::Escape),
+            ("c\"", Class::String),
+            ("'", Class::String),
+            ("\\'", Class::Escape),
+            ("'", Class::String),
+            ("b\"", Class::String),
+            ("\\x7f", Class::Escape),
+            // Invalid escapes are left alone.
+            ("\\q\"", Class::String),
+        ]
+    );
+
+    // Literals without escapes and raw strings are still a single token.
+    let tokens = classify_with(r##"("plain", r#"\n"#)"##, options);
+    assert_class(&tokens, "\"plain\"", Some(Class::String));
+    assert_class(&tokens, r##"r#"\n"#"##, Some(Class::String));
+
+    let tokens = classify(src);
+    assert_class(&tokens, "\"a\\nb\\u{1F600}c\"", Some(Class::String));
+}
+
+#[test]
+fn test_edition_keywords() {
+    // `async`, `await`, `dyn` and `try` are only reserved from the 2018 edition on.
+    let src = "async fn f(x: &dyn T) { try { x.await } }";
+    for &edition in ALL_EDITIONS {
+        let tokens = classify_edition(src, edition, HighlightOptions::default());
+        let class = if edition >= Edition::Edition2018 { Class::KeyWord } else { Class::Ident };
+        for keyword in &["async", "dyn", "try", "await"] {
+            assert_class(&tokens, keyword, Some(class));
+        }
+        assert_class(&tokens, "fn", Some(Class::KeyWord));
+    }
+}
+
+#[test]
+fn test_deterministic_output() {
+    let render = || {
+        let mut html = Buffer::new();
+        let src = include_str!("fixtures/sample.rs");
+        write_code(&mut html, src, Edition::Edition2018, HighlightOptions::default());
+        html.into_inner()
+    };
+    let first = render();
+    assert_eq!(render(), first);
+
+    // Highlighting interns symbols in its own session, so neither an enclosing
+    // session nor whatever was interned in it may change the output.
+    let nested = with_session_globals(Edition::Edition2021, || {
+        for ident in &["foo", "bar", "main", "Option"] {
+            Symbol::intern(ident);
+        }
+        render()
+    });
+    assert_eq!(nested, first);
+}
+
+#[test]
+fn test_f_string_prefix() {
+    // A doc-test example as it reaches the highlighter.
+    let src = "let name = \"world\";\nprintln!(f\"hello {name}\");\n";
+    let tokens = classify(src);
+    assert_class(&tokens, "f", Some(Class::String));
+    assert_class(&tokens, "\"hello {name}\"", Some(Class::String));
+    assert_class(&tokens, "println", Some(Class::Macro));
+
+    let mut html = Buffer::new();
+    write_code(&mut html, src, Edition::Edition2018, HighlightOptions::default());
+    assert!(html.into_inner().contains(
+        "<span class=\"string\">f</span><span class=\"string\">&quot;hello {name}&quot;</span>"
+    ));
+
+    // The string goes through the usual literal handling.
+    let options = HighlightOptions { highlight_escapes: true, ..HighlightOptions::default() };
+    assert_eq!(
+        classify_with(r#"f"a\n""#, options),
+        vec![
+            ("f".to_string(), Some(Class::String)),
+            ("\"a".to_string(), Some(Class::String)),
+            ("\\n".to_string(), Some(Class::Escape)),
+            ("\"".to_string(), Some(Class::String)),
+        ]
+    );
+
+    // A plain `f` identifier is left alone.
+    let tokens = classify("let f = \"a\"; f(\"b\");");
+    assert_class(&tokens, "f", Some(Class::Ident));
+}
+
+#[test]
+fn test_tooltip() {
+    fn tooltip(options: HighlightOptions<'_>) -> String {
+        let mut html = Buffer::new();
+        let tooltip = Some((Some(Edition::Edition2018), "edition"));
+        render_with_highlighting(
+            "x",
+            &mut html,
+            None,
+            None,
+            tooltip,
+            Edition::Edition2018,
+            options,
+        );
+        let html = html.into_inner();
+        html[..html.find("<div class=\"example-wrap\">").unwrap()].to_string()
+    }
+
+    assert_eq!(
+        tooltip(HighlightOptions::default()),
+        "<div class='information'><div class='tooltip edition' data-edition=\"2018\">ⓘ</div></div>"
+    );
+    let options = HighlightOptions {
+        tooltip_class: Some("hint"),
+        tooltip_glyph: Some("?"),
+        ..HighlightOptions::default()
+    };
+    assert_eq!(
+        tooltip(options),
+        "<div class='information'><div class='hint edition' data-edition=\"2018\">?</div></div>"
+    );
+}
+
+#[test]
+fn test_builtin_macros() {
+    let src = "println!(\"{}\", my_macro!(vec![1]));";
+    let options =
+        HighlightOptions { highlight_builtin_macros: true, ..HighlightOptions::default() };
+    let tokens = classify_with(src, options);
+    assert_eq!(
+        tokens.iter().filter(|(_, class)| class.is_some()).collect::<Vec<_>>(),
+        vec![
+            &("println".to_string(), Some(Class::BuiltinMacro)),
+            &("!".to_string(), Some(Class::BuiltinMacro)),
+            &("\"{}\"".to_string(), Some(Class::String)),
+            &("my_macro".to_string(), Some(Class::Macro)),
+            &("!".to_string(), Some(Class::Macro)),
+            &("vec".to_string(), Some(Class::BuiltinMacro)),
+            &("!".to_string(), Some(Class::BuiltinMacro)),
+            &("1".to_string(), Some(Class::Number)),
+        ]
+    );
+
+    let tokens = classify(src);
+    assert_class(&tokens, "println", Some(Class::Macro));
+    assert_class(&tokens, "!", Some(Class::Macro));
+
+    let options = HighlightOptions { builtin_macros: Some(&["my_macro"]), ..options };
+    let tokens = classify_with(src, options);
+    assert_class(&tokens, "println", Some(Class::Macro));
+    assert_class(&tokens, "my_macro", Some(Class::BuiltinMacro));
+}
+
+#[test]
+fn test_linkable_idents() {
+    let src = "let foo: Foo = Foo::new(bar, Option::None);";
+    let options =
+        HighlightOptions { linkable_idents: &["Foo", "bar"], ..HighlightOptions::default() };
+    let tokens = classify_with(src, options);
+    assert_class(&tokens, "Foo", Some(Class::LinkableIdent));
+    assert_class(&tokens, "bar", Some(Class::LinkableIdent));
+    assert_class(&tokens, "foo", Some(Class::Ident));
+    assert_class(&tokens, "new", Some(Class::Ident));
+    assert_class(&tokens, "Option", Some(Class::PreludeTy));
+
+    let mut html = Buffer::new();
+    write_code(&mut html, "bar()", Edition::Edition2018, options);
+    assert_eq!(html.into_inner(), "<span class=\"ident linkable\">bar</span>()");
+}
+
+#[test]
+fn test_wrappers() {
+    fn render(skip_example_wrap: bool, skip_pre: bool) -> String {
+        let mut html = Buffer::new();
+        let options =
+            HighlightOptions { skip_example_wrap, skip_pre, ..HighlightOptions::default() };
+        let button = Some("<a>Run</a>");
+        render_with_highlighting(
+            "x",
+            &mut html,
+            Some("foo"),
+            button,
+            None,
+            Edition::Edition2018,
+            options,
+        );
+        html.into_inner()
+    }
+
+    let x = "<span class=\"ident\">x</span>";
+    assert_eq!(
+        render(false, false),
+        format!(
+            "<div class=\"example-wrap\"><pre class=\"rust foo\">\n{}</pre><a>Run</a></div>\n",
+            x
+        )
+    );
+    assert_eq!(render(true, false), format!("<pre class=\"rust foo\">\n{}</pre><a>Run</a>\n", x));
+    assert_eq!(render(false, true), format!("<div class=\"example-wrap\">{}<a>Run</a></div>\n", x));
+    assert_eq!(render(true, true), format!("{}<a>Run</a>\n", x));
+}
+
+#[test]
+fn test_token_hook() {
+    let link = |class: Class, text: &str| match (class, text) {
+        (Class::Ident, "Foo") => {
+            Some("<a class=\"ident\" href=\"struct.Foo.html\">Foo</a>".to_string())
+        }
+        _ => None,
+    };
+    for &coalesce_spans in &[false, true] {
+        let mut html = Buffer::new();
+        let options = HighlightOptions {
+            token_hook: Some(&link),
+            coalesce_spans,
+            ..HighlightOptions::default()
+        };
+        write_code(&mut html, "let foo: Foo = Foo::new();", Edition::Edition2018, options);
+        assert_eq!(
+            html.into_inner(),
+            "<span class=\"kw\">let</span> <span class=\"ident\">foo</span>: \
+             <a class=\"ident\" href=\"struct.Foo.html\">Foo</a> <span class=\"op\">=</span> \
+             <a class=\"ident\" href=\"struct.Foo.html\">Foo</a><span class=\"op\">::</span>\
+             <span class=\"ident\">new</span>();"
+        );
+    }
+
+    // The hook sees single tokens even when spans are merged, and the tokens
+    // it replaces aren't merged with their neighbours.
+    let seen = RefCell::new(Vec::new());
+    let record = |class: Class, text: &str| {
+        seen.borrow_mut().push((class, text.to_string()));
+        link(class, text)
+    };
+    let mut html = Buffer::new();
+    let options = HighlightOptions {
+        token_hook: Some(&record),
+        coalesce_spans: true,
+        ..HighlightOptions::default()
+    };
+    write_code(&mut html, "m!(Foo Foo a b)", Edition::Edition2018, options);
+    assert_eq!(
+        html.into_inner(),
+        "<span class=\"macro\">m!</span>(<a class=\"ident\" href=\"struct.Foo.html\">Foo</a> \
+         <a class=\"ident\" href=\"struct.Foo.html\">Foo</a> <span class=\"ident\">a b</span>)"
+    );
+    assert_eq!(
+        seen.into_inner(),
+        vec![
+            (Class::Macro, "m".to_string()),
+            (Class::Macro, "!".to_string()),
+            (Class::Ident, "Foo".to_string()),
+            (Class::Ident, "Foo".to_string()),
+            (Class::Ident, "a".to_string()),
+            (Class::Ident, "b".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_classified_ranges() {
+    let src = "#[derive(Debug)]\nfn main() {\n    let x = &y && z; // done\n}\n";
+    let ranges: Vec<_> =
+        classified_ranges(src, Edition::Edition2018, HighlightOptions::default()).collect();
+
+    // The ranges cover the whole source, in order and without gaps.
+    let mut pos = 0;
+    for (_, range) in &ranges {
+        assert_eq!(range.start, pos);
+        assert!(range.end > range.start);
+        pos = range.end;
+    }
+    assert_eq!(pos, src.len());
+
+    let class_of = |text: &str| {
+        ranges.iter().find(|(_, range)| &src[range.clone()] == text).map(|(class, _)| *class)
+    };
+    assert_eq!(class_of("["), Some(Some(Class::Attribute)));
+    assert_eq!(class_of("derive"), Some(Some(Class::Ident)));
+    assert_eq!(class_of("fn"), Some(Some(Class::KeyWord)));
+    assert_eq!(class_of("&"), Some(Some(Class::RefKeyWord)));
+    assert_eq!(class_of("&&"), Some(Some(Class::Op)));
+    assert_eq!(class_of("// done"), Some(Some(Class::Comment)));
+    assert_eq!(class_of(";"), Some(None));
+}
@@ -207,6 +207,7 @@ fn next(&mut self) -> Option<Self::Item> {
         let should_panic;
         let ignore;
         let edition;
+        let runnable;
         if let Some(Event::Start(Tag::CodeBlock(kind))) = event {
             let parse_result = match kind {
                 CodeBlockKind::Fenced(ref lang) => {
@@ -219,6 +220,7 @@ fn next(&mut self) -> Option<Self::Item> {
             }
             compile_fail = parse_result.compile_fail;
             should_panic = parse_result.should_panic;
+            runnable = parse_result.is_runnable();
             ignore = parse_result.ignore;
             edition = parse_result.edition;
         } else {
@@ -241,7 +243,8 @@ fn next(&mut self) -> Option<Self::Item> {
         let lines = origtext.lines().filter_map(|l| map_line(l).for_html());
         let text = lines.collect::<Vec<Cow<'_, str>>>().join("\n");
 
-        let playground_button = self.playground.as_ref().and_then(|playground| {
+        let playground = self.playground.as_ref().filter(|_| runnable);
+        let playground_button = playground.and_then(|playground| {
             let krate = &playground.crate_name;
             let url = &playground.url;
             if url.is_empty() {
@@ -315,6 +318,7 @@ fn dont_escape(c: u8) -> bool {
             playground_button.as_deref(),
             tooltip,
             edition,
+            highlight::HighlightOptions::default(),
         );
         Some(Event::Html(s.into_inner().into()))
     }
@@ -779,6 +783,14 @@ fn parse_without_check(
         Self::parse(string, allow_error_code_check, enable_per_target_ignores, None)
     }
 
+    /// Returns whether the block should get a playground "Run" button.
+    ///
+    /// Blocks that aren't meant to be run as written (`ignore`, `no_run` and
+    /// `compile_fail`) don't get one.
+    crate fn is_runnable(&self) -> bool {
+        self.ignore == Ignore::None && !self.no_run && !self.compile_fail
+    }
+
     fn tokens(string: &str) -> impl Iterator<Item = &str> {
         // Pandoc, which Rust once used for generating documentation,
         // expects lang strings to be surrounded by `{}` and for each token
@@ -1,5 +1,5 @@
 use super::{plain_text_summary, short_markdown_summary};
-use super::{ErrorCodes, IdMap, Ignore, LangString, Markdown, MarkdownHtml};
+use super::{ErrorCodes, IdMap, Ignore, LangString, Markdown, MarkdownHtml, Playground};
 use rustc_span::edition::{Edition, DEFAULT_EDITION};
 use std::cell::RefCell;
 
@@ -292,3 +292,21 @@ fn t(input: &str, expect: &str) {
     t("Struct<'a, T>", "<p>Struct&lt;’a, T&gt;</p>\n");
     t("Struct<br>", "<p>Struct&lt;br&gt;</p>\n");
 }
+
+#[test]
+fn test_playground_button() {
+    fn t(input: &str, expect: bool) {
+        let mut map = IdMap::new();
+        let playground =
+            Some(Playground { crate_name: None, url: "https://play.rust-lang.org/".into() });
+        let output = Markdown(input, &[], &mut map, ErrorCodes::Yes, DEFAULT_EDITION, &playground)
+            .into_string();
+        assert_eq!(output.contains("class=\"test-arrow\""), expect, "original: {}", input);
+    }
+
+    t("```\nfn main() {}\n```", true);
+    t("```should_panic\nfn main() { panic!() }\n```", true);
+    t("```ignore\nfn main() {}\n```", false);
+    t("```no_run\nfn main() {}\n```", false);
+    t("```compile_fail\nfn main() {}\n```", false);
+}
@@ -310,6 +310,8 @@ fn init(
             generate_search_filter,
             unstable_features,
             generate_redirect_map,
+            highlight_prelude_types,
+            highlight_prelude_values,
             ..
         } = options;
 
@@ -383,6 +385,8 @@ fn init(
             edition,
             codes: ErrorCodes::from(unstable_features.is_nightly_build()),
             playground,
+            highlight_prelude_types,
+            highlight_prelude_values,
         };
 
         // Add the default themes to the `Vec` of stylepaths
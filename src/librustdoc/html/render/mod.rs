@@ -69,6 +69,7 @@
     href, print_abi_with_space, print_default_space, print_generic_bounds, Buffer, Function,
     PrintWithSpace, WhereClause,
 };
+use crate::html::highlight::HighlightOptions;
 use crate::html::layout;
 use crate::html::markdown::{self, ErrorCodes, Markdown, MarkdownHtml, MarkdownSummaryLine};
 
@@ -120,6 +121,10 @@
     crate edition: Edition,
     crate codes: ErrorCodes,
     playground: Option<markdown::Playground>,
+    /// Identifiers highlighted like `Option` and `Result` in source code.
+    highlight_prelude_types: Vec<String>,
+    /// Identifiers highlighted like `Some` and `None` in source code.
+    highlight_prelude_values: Vec<String>,
 }
 
 impl SharedContext<'_> {
@@ -133,6 +138,19 @@ impl SharedContext<'_> {
         Ok(())
     }
 
+    /// Calls `f` with the options to highlight source code with.
+    crate fn with_highlight_options<R>(&self, f: impl FnOnce(HighlightOptions<'_>) -> R) -> R {
+        let prelude_types: Vec<_> =
+            self.highlight_prelude_types.iter().map(String::as_str).collect();
+        let prelude_values: Vec<_> =
+            self.highlight_prelude_values.iter().map(String::as_str).collect();
+        f(HighlightOptions {
+            prelude_types: &prelude_types,
+            prelude_values: &prelude_values,
+            ..HighlightOptions::default()
+        })
+    }
+
     /// Based on whether the `collapse-docs` pass was run, return either the `doc_value` or the
     /// `collapsed_doc_value` of the given item.
     crate fn maybe_collapsed_doc_value<'a>(&self, item: &'a clean::Item) -> Option<String> {
@@ -931,14 +931,17 @@ fn item_enum(w: &mut Buffer, cx: &Context<'_>, it: &clean::Item, e: &clean::Enum
 
 fn item_macro(w: &mut Buffer, cx: &Context<'_>, it: &clean::Item, t: &clean::Macro) {
     wrap_into_docblock(w, |w| {
-        highlight::render_with_highlighting(
-            &t.source,
-            w,
-            Some("macro"),
-            None,
-            None,
-            it.source.span().edition(),
-        );
+        cx.shared.with_highlight_options(|options| {
+            highlight::render_with_highlighting(
+                &t.source,
+                w,
+                Some("macro"),
+                None,
+                None,
+                it.source.span().edition(),
+                options,
+            )
+        });
     });
     document(w, cx, it, None)
 }
@@ -131,7 +131,11 @@ fn emit_source(&mut self, filename: &FileName) -> Result<(), Error> {
             &self.scx.layout,
             &page,
             "",
-            |buf: &mut _| print_src(buf, contents, self.scx.edition),
+            |buf: &mut _| {
+                self.scx.with_highlight_options(|options| {
+                    print_src(buf, contents, self.scx.edition, options)
+                })
+            },
             &self.scx.style_files,
         );
         self.scx.fs.write(&cur, v.as_bytes())?;
@@ -169,7 +173,12 @@ fn emit_source(&mut self, filename: &FileName) -> Result<(), Error> {
 
 /// Wrapper struct to render the source code of a file. This will do things like
 /// adding line numbers to the left-hand side.
-fn print_src(buf: &mut Buffer, s: &str, edition: Edition) {
+fn print_src(
+    buf: &mut Buffer,
+    s: &str,
+    edition: Edition,
+    options: highlight::HighlightOptions<'_>,
+) {
     let lines = s.lines().count();
     let mut cols = 0;
     let mut tmp = lines;
@@ -182,5 +191,5 @@ fn print_src(buf: &mut Buffer, s: &str, edition: Edition) {
         write!(buf, "<span id=\"{0}\">{0:1$}</span>\n", i, cols);
     }
     buf.write_str("</pre>");
-    highlight::render_with_highlighting(s, buf, None, None, None, edition);
+    highlight::render_with_highlighting(s, buf, None, None, None, edition, options);
 }
@@ -524,6 +524,22 @@ fn opts() -> Vec<RustcOptGroup> {
                 "Generate JSON file at the top level instead of generating HTML redirection files",
             )
         }),
+        unstable("highlight-prelude-type", |o| {
+            o.optmulti(
+                "",
+                "highlight-prelude-type",
+                "Identifier to highlight like `Option` in source code",
+                "NAME",
+            )
+        }),
+        unstable("highlight-prelude-value", |o| {
+            o.optmulti(
+                "",
+                "highlight-prelude-value",
+                "Identifier to highlight like `Some` in source code",
+                "NAME",
+            )
+        }),
         unstable("print", |o| {
             o.optmulti("", "print", "Rustdoc information to print on stdout", "[unversioned-files]")
         }),
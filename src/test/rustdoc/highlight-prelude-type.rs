@@ -0,0 +1,18 @@
+// compile-flags:-Z unstable-options --highlight-prelude-type Meters --highlight-prelude-value ZERO
+
+// @has src/highlight_prelude_type/highlight-prelude-type.rs.html
+// @has - '//span[@class="prelude-ty"]' 'Meters'
+// @has - '//span[@class="prelude-val"]' 'ZERO'
+// @!has - '//span[@class="prelude-ty"]' 'Feet'
+pub struct Meters(pub u32);
+pub struct Feet(pub u32);
+
+pub const ZERO: Meters = Meters(0);
+
+// @has highlight_prelude_type/macro.meters.html '//span[@class="prelude-ty"]' 'Meters'
+#[macro_export]
+macro_rules! meters {
+    ($n:expr) => {
+        $crate::Meters($n)
+    };
+}